@@ -0,0 +1,138 @@
+use std::sync::Arc;
+
+use vulkano::buffer::{BufferUsage, CpuAccessibleBuffer};
+use vulkano::command_buffer::{AutoCommandBufferBuilder, DynamicState};
+use vulkano::descriptor::descriptor_set::PersistentDescriptorSet;
+use vulkano::descriptor::pipeline_layout::PipelineLayoutAbstract;
+use vulkano::device::{Device, DeviceExtensions};
+use vulkano::format::Format;
+use vulkano::framebuffer::{Framebuffer, Subpass};
+use vulkano::image::{AttachmentImage, ImageUsage};
+use vulkano::instance::{Instance, InstanceExtensions, PhysicalDevice};
+use vulkano::pipeline::{GraphicsPipeline, GraphicsPipelineAbstract, vertex::BufferlessVertices, viewport::Viewport};
+use vulkano::sync::{now, GpuFuture};
+
+use crate::shaders::{fs, vs, view_proj_push_constants};
+use crate::skybox::load_skybox;
+
+/// Renders a single frame of the bufferless skybox into an off-screen
+/// `AttachmentImage` (no surface, no swapchain, no event loop) and writes it
+/// out as a PNG. Lets the crate be used for automated image tests and for
+/// generating frames on machines with no display.
+pub fn render_headless(width: u32, height: u32, out_path: &str) {
+    let instance = Instance::new(None, &InstanceExtensions::none(), None)
+        .expect("failed to create Vulkan instance");
+
+    let physical = PhysicalDevice::enumerate(&instance)
+        .next()
+        .expect("no device available");
+    println!("Using device: {} (type: {:?})", physical.name(), physical.ty());
+
+    let queue_family = physical
+        .queue_families()
+        .find(|&q| q.supports_graphics())
+        .expect("couldn't find a graphical queue family");
+
+    let (device, mut queues) = Device::new(physical, physical.supported_features(),
+                                            &DeviceExtensions::none(),
+                                            [(queue_family, 0.5)].iter().cloned())
+        .expect("failed to create device");
+
+    let queue = queues.next().unwrap();
+
+    let format = Format::R8G8B8A8Srgb;
+
+    let vs = vs::Shader::load(device.clone()).expect("failed to create shader module");
+    let fs = fs::Shader::load(device.clone()).expect("failed to create shader module");
+    let render_pass = Arc::new(single_pass_renderpass!(device.clone(),
+        attachments: {
+            color: {
+                load: Clear,
+                store: Store,
+                format: format,
+                samples: 1,
+            }
+        },
+        pass: {
+            color: [color],
+            depth_stencil: {}
+        }
+    ).unwrap());
+
+    let pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync> = Arc::new(GraphicsPipeline::start()
+        .cull_mode_front()
+        .front_face_counter_clockwise()
+        .vertex_shader(vs.main_entry_point(), ())
+        .viewports_dynamic_scissors_irrelevant(1)
+        .fragment_shader(fs.main_entry_point(), ())
+        .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
+        .build(device.clone())
+        .unwrap());
+
+    let (skybox_image, sampler, skybox_future) = load_skybox(device.clone(), queue.clone());
+
+    let layout = pipeline.descriptor_set_layout(0).unwrap();
+    let skybox_set = Arc::new(PersistentDescriptorSet::start(layout.clone())
+        .add_sampled_image(skybox_image, sampler)
+        .unwrap()
+        .build()
+        .unwrap());
+
+    let target = AttachmentImage::with_usage(device.clone(), [width, height], format,
+        ImageUsage { color_attachment: true, transfer_source: true, .. ImageUsage::none() })
+        .expect("failed to create offscreen render target");
+
+    let framebuffer = Arc::new(Framebuffer::start(render_pass.clone())
+        .add(target.clone())
+        .unwrap()
+        .build()
+        .unwrap());
+
+    let output_buffer = CpuAccessibleBuffer::from_iter(device.clone(), BufferUsage::transfer_destination(),
+        (0 .. width * height * 4).map(|_| 0u8))
+        .expect("failed to create output buffer");
+
+    let dynamic_state = DynamicState {
+        line_width: None,
+        viewports: Some(vec![Viewport {
+            origin: [0.0, 0.0],
+            dimensions: [width as f32, height as f32],
+            depth_range: 0.0 .. 1.0,
+        }]),
+        scissors: None,
+    };
+
+    let push_constants = view_proj_push_constants(width as f32 / height as f32, 0.0);
+
+    let command_buffer =
+        AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue.family())
+            .unwrap()
+            .begin_render_pass(framebuffer.clone(), false, vec![[0.0, 0.0, 1.0, 1.0].into()])
+            .unwrap()
+            .draw(pipeline.clone(),
+                &dynamic_state,
+                BufferlessVertices{ vertices: 3, instances: 1 },
+                skybox_set.clone(),
+                push_constants)
+            .unwrap()
+            .end_render_pass()
+            .unwrap()
+            .copy_image_to_buffer(target.clone(), output_buffer.clone())
+            .unwrap()
+            .build()
+            .unwrap();
+
+    let finished = skybox_future
+        .join(now(device.clone()))
+        .then_execute(queue.clone(), command_buffer)
+        .unwrap()
+        .then_signal_fence_and_flush()
+        .expect("failed to flush offscreen render");
+    finished.wait(None).unwrap();
+
+    let buffer_content = output_buffer.read().unwrap();
+    image::save_buffer(out_path, &buffer_content, width, height, image::ColorType::RGBA(8))
+        .expect("failed to write PNG");
+
+    println!("Wrote offscreen render to {}", out_path);
+}
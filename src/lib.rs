@@ -0,0 +1,22 @@
+#[macro_use]
+extern crate vulkano;
+extern crate vulkano_shaders;
+extern crate vulkano_win;
+extern crate winit;
+extern crate cgmath;
+extern crate image;
+extern crate shaderc;
+
+pub mod shaders;
+pub mod skybox;
+pub mod surface;
+pub mod swapchain;
+pub mod live_shader;
+mod mip_image;
+pub mod texture_array;
+pub mod renderer;
+pub mod headless;
+
+pub use renderer::BufferlessRenderer;
+pub use surface::SurfaceBinding;
+pub use swapchain::SwapchainBinding;
@@ -0,0 +1,202 @@
+use std::borrow::Cow;
+use std::ffi::CStr;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use vulkano::descriptor::descriptor::{DescriptorDesc, ShaderStages};
+use vulkano::descriptor::pipeline_layout::{PipelineLayoutDesc, PipelineLayoutDescPcRange};
+use vulkano::device::Device;
+use vulkano::format::Format;
+use vulkano::framebuffer::RenderPassAbstract;
+use vulkano::pipeline::shader::{GraphicsShaderType, ShaderInterfaceDef, ShaderInterfaceDefEntry, ShaderModule};
+use vulkano::pipeline::{GraphicsPipeline, GraphicsPipelineAbstract};
+
+use crate::shaders::vs;
+use crate::swapchain::subpass;
+
+/// The fixed uniform contract every live-reloaded fragment shader is
+/// compiled against: a Shadertoy-style `iResolution`/`iTime`/`iMouse` push
+/// constant block, matching `v_clip_pos` as its only input and a single
+/// `vec4` color as its only output.
+///
+/// `_pad0` exists only to match std430's alignment: GLSL aligns `vec2
+/// iMouse` to a 16-byte offset after `vec2 iResolution; float iTime;`, so
+/// without it `i_mouse` would land 4 bytes short of where the shader reads it.
+#[derive(Copy, Clone)]
+#[repr(C)]
+pub struct ShaderToyPushConstants {
+    pub i_resolution: [f32; 2],
+    pub i_time: f32,
+    pub _pad0: f32,
+    pub i_mouse: [f32; 2],
+}
+
+#[derive(Debug, Copy, Clone)]
+struct FsInput;
+
+unsafe impl ShaderInterfaceDef for FsInput {
+    type Iter = FsInputIter;
+    fn elements(&self) -> FsInputIter {
+        FsInputIter(0)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct FsInputIter(u16);
+
+impl Iterator for FsInputIter {
+    type Item = ShaderInterfaceDefEntry;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.0 {
+            0 => {
+                self.0 += 1;
+                Some(ShaderInterfaceDefEntry {
+                    location: 0 .. 1,
+                    format: Format::R32G32Sfloat,
+                    name: Some(Cow::Borrowed("v_clip_pos")),
+                })
+            },
+            _ => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (1 - self.0) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for FsInputIter {}
+
+#[derive(Debug, Copy, Clone)]
+struct FsOutput;
+
+unsafe impl ShaderInterfaceDef for FsOutput {
+    type Iter = FsOutputIter;
+    fn elements(&self) -> FsOutputIter {
+        FsOutputIter(0)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct FsOutputIter(u16);
+
+impl Iterator for FsOutputIter {
+    type Item = ShaderInterfaceDefEntry;
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.0 {
+            0 => {
+                self.0 += 1;
+                Some(ShaderInterfaceDefEntry {
+                    location: 0 .. 1,
+                    format: Format::R32G32B32A32Sfloat,
+                    name: Some(Cow::Borrowed("f_color")),
+                })
+            },
+            _ => None,
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (1 - self.0) as usize;
+        (len, Some(len))
+    }
+}
+
+impl ExactSizeIterator for FsOutputIter {}
+
+#[derive(Debug, Copy, Clone)]
+struct FsLayout(ShaderStages);
+
+unsafe impl PipelineLayoutDesc for FsLayout {
+    fn num_sets(&self) -> usize { 0 }
+    fn num_bindings_in_set(&self, _set: usize) -> Option<usize> { None }
+    fn descriptor(&self, _set: usize, _binding: usize) -> Option<DescriptorDesc> { None }
+    fn num_push_constants_ranges(&self) -> usize { 1 }
+    fn push_constants_range(&self, num: usize) -> Option<PipelineLayoutDescPcRange> {
+        if num != 0 || self.0 == ShaderStages::none() {
+            return None;
+        }
+        Some(PipelineLayoutDescPcRange {
+            offset: 0,
+            size: std::mem::size_of::<ShaderToyPushConstants>(),
+            stages: self.0,
+        })
+    }
+}
+
+fn mtime(path: &PathBuf) -> SystemTime {
+    fs::metadata(path).and_then(|m| m.modified()).unwrap_or(SystemTime::UNIX_EPOCH)
+}
+
+/// Watches a fragment shader source file and recompiles it with `shaderc`
+/// whenever its mtime changes, rebuilding just the `GraphicsPipeline` while
+/// reusing the existing device, swapchain, and render pass.
+pub struct LiveFragmentShader {
+    path: PathBuf,
+    last_modified: SystemTime,
+}
+
+impl LiveFragmentShader {
+    pub fn new(path: impl Into<PathBuf>) -> LiveFragmentShader {
+        let path = path.into();
+        let last_modified = mtime(&path);
+        LiveFragmentShader { path, last_modified }
+    }
+
+    /// Returns `true` whenever the watched file's mtime has changed since
+    /// the last poll (or since construction, for the first call) — so the
+    /// first poll of a file that hasn't been touched since `new()` returns
+    /// `false`.
+    pub fn poll_changed(&mut self) -> bool {
+        let modified = mtime(&self.path);
+        if modified != self.last_modified {
+            self.last_modified = modified;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Compiles the current file contents to SPIR-V and builds a fresh
+    /// bufferless-triangle pipeline against it.
+    pub fn build_pipeline(&self, device: Arc<Device>, render_pass: Arc<dyn RenderPassAbstract + Send + Sync>)
+        -> Arc<dyn GraphicsPipelineAbstract + Send + Sync>
+    {
+        let source = fs::read_to_string(&self.path)
+            .unwrap_or_else(|e| panic!("failed to read fragment shader {}: {}", self.path.display(), e));
+
+        let mut compiler = shaderc::Compiler::new().expect("failed to create shaderc compiler");
+        let artifact = compiler
+            .compile_into_spirv(&source, shaderc::ShaderKind::Fragment,
+                &self.path.to_string_lossy(), "main", None)
+            .unwrap_or_else(|e| panic!("failed to compile {}: {}", self.path.display(), e));
+
+        let fs_module = unsafe { ShaderModule::new(device.clone(), artifact.as_binary_u8()) }
+            .expect("failed to load recompiled shader module");
+
+        let fs_entry_point = unsafe {
+            fs_module.graphics_entry_point(
+                CStr::from_bytes_with_nul(b"main\0").unwrap(),
+                FsInput,
+                FsOutput,
+                FsLayout(ShaderStages { fragment: true, .. ShaderStages::none() }),
+                GraphicsShaderType::Fragment,
+            )
+        };
+
+        let vs = vs::Shader::load(device.clone()).expect("failed to create shader module");
+
+        Arc::new(GraphicsPipeline::start()
+            .cull_mode_front()
+            .front_face_counter_clockwise()
+            .vertex_shader(vs.main_entry_point(), ())
+            .viewports_dynamic_scissors_irrelevant(1)
+            .fragment_shader(fs_entry_point, ())
+            .render_pass(subpass(&render_pass))
+            .build(device)
+            .unwrap())
+    }
+}
@@ -1,253 +1,100 @@
-#[macro_use]
-extern crate vulkano;
-extern crate vulkano_shaders;
+extern crate vulkano_bufferless;
 extern crate winit;
-extern crate vulkano_win;
 
-use vulkano_win::VkSurfaceBuild;
+use vulkano_bufferless::{BufferlessRenderer, SurfaceBinding};
 
-use vulkano::command_buffer::{AutoCommandBufferBuilder, DynamicState};
-use vulkano::device::{Device, DeviceExtensions};
-use vulkano::framebuffer::{Framebuffer, Subpass};
-use vulkano::instance::{Instance, PhysicalDevice};
-use vulkano::pipeline::{GraphicsPipeline, vertex::BufferlessVertices, viewport::Viewport};
-use vulkano::swapchain;
-use vulkano::swapchain::{
-    PresentMode, SurfaceTransform, Swapchain, 
-    AcquireError, SwapchainCreationError
-};
-use vulkano::sync::{FlushError, GpuFuture, now};
+use winit::{ElementState, Event, EventsLoop, KeyboardInput, VirtualKeyCode, WindowEvent};
 
-use winit::{Event, EventsLoop, WindowBuilder, WindowEvent};
+/// Parses `--headless WIDTHxHEIGHT --out file.png` from the process
+/// arguments. Returns `None` (and the caller falls back to the windowed
+/// path) unless both flags are present.
+fn parse_headless_args(args: &[String]) -> Option<(u32, u32, String)> {
+    let headless_pos = args.iter().position(|a| a == "--headless")?;
+    let dims = args.get(headless_pos + 1)?;
+    let mut parts = dims.split('x');
+    let width = parts.next()?.parse().ok()?;
+    let height = parts.next()?.parse().ok()?;
 
-use std::sync::Arc;
+    let out_pos = args.iter().position(|a| a == "--out")?;
+    let out_path = args.get(out_pos + 1)?.clone();
 
-fn main() {
-    let instance = {
-        let extensions = vulkano_win::required_extensions();
-        Instance::new(None, &extensions, None)
-            .expect("failed to create Vulkan instance")
-    };
-
-    let physical = PhysicalDevice::enumerate(&instance)
-        .next()
-        .expect("no device available");
-    println!("Using device: {} (type: {:?})", physical.name(), physical.ty());
-
-    let mut events_loop = EventsLoop::new();
-    let surface = WindowBuilder::new()
-        .build_vk_surface(&events_loop, instance.clone())
-        .unwrap();
-    let queue_family = physical
-        .queue_families()
-        .find(|&q| {
-            q.supports_graphics() && surface.is_supported(q).unwrap_or(false)
-        })
-        .expect("couldn't find a graphical queue family");
-
-    let (device, mut queues) = {
-        let device_ext = DeviceExtensions {
-            khr_swapchain: true,
-            .. DeviceExtensions::none()
-        };
-
-        Device::new(physical, physical.supported_features(), &device_ext,
-                    [(queue_family, 0.5)].iter().cloned())
-            .expect("failed to create device")
-    };
-
-    let queue = queues.next().unwrap();
-    let mut dimensions;
-    let (mut swapchain, mut images) = {
-        let caps = surface
-            .capabilities(physical)
-            .expect("failed to get surface capabilities");
-
-        dimensions = caps.current_extent.unwrap_or([1024, 768]);
-        let alpha = caps.supported_composite_alpha.iter().next().unwrap();
-        let format = caps.supported_formats[0].0;
-
-        Swapchain::new(device.clone(), surface.clone(), caps.min_image_count, format,
-                       dimensions, 1, caps.supported_usage_flags, &queue,
-                       SurfaceTransform::Identity, alpha, PresentMode::Fifo, true,
-                       None)
-            .expect("failed to create swapchain")
-    };
-    
-    //Note the vertex shader takes no inputs and instead uses gl_VertexIndex
-    //to create verticies
-    mod vs {
-        vulkano_shaders::shader!{
-            ty: "vertex",
-            src: "
-#version 450
-
-layout(location = 0) out vec2 v_screen_coords;
-
-void main() {
-    v_screen_coords = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2);
-	gl_Position = vec4(v_screen_coords * 2.0f + -1.0f, 0.0f, 1.0f);
+    Some((width, height, out_path))
 }
-"
-        }
-    }
 
-    mod fs {
-        vulkano_shaders::shader!{
-            ty: "fragment",
-            src: "
-#version 450
+/// Parses `--shader path/to/fragment.frag`, putting the renderer into
+/// Shadertoy-playground mode against that file instead of drawing the
+/// built-in skybox.
+fn parse_shader_arg(args: &[String]) -> Option<String> {
+    let shader_pos = args.iter().position(|a| a == "--shader")?;
+    args.get(shader_pos + 1).cloned()
+}
 
-layout(location = 0) in vec2 v_screen_coords;
-layout(location = 0) out vec4 f_color;
+/// Parses `--layers a.png b.png ...`, putting the renderer into
+/// texture-array mode over those images. Collects every argument up to the
+/// next flag (or the end of the argument list).
+fn parse_layers_arg(args: &[String]) -> Option<Vec<String>> {
+    let layers_pos = args.iter().position(|a| a == "--layers")?;
+    let paths: Vec<String> = args[layers_pos + 1 ..].iter()
+        .take_while(|a| !a.starts_with("--"))
+        .cloned()
+        .collect();
+
+    if paths.is_empty() { None } else { Some(paths) }
+}
 
-void main() {
-    f_color = vec4(v_screen_coords, 0.0, 1.0);
+enum Mode {
+    Skybox,
+    LiveShader(String),
+    TextureArray(Vec<String>),
 }
-"
-        }
-    }
 
-    let vs = vs::Shader::load(device.clone()).expect("failed to create shader module");
-    let fs = fs::Shader::load(device.clone()).expect("failed to create shader module");
-    let render_pass = Arc::new(single_pass_renderpass!(device.clone(),
-        attachments: {
-            color: {
-                load: Clear,
-                store: Store,
-                format: swapchain.format(),
-                samples: 1,
-            }
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+
+    match parse_headless_args(&args) {
+        Some((width, height, out_path)) => vulkano_bufferless::headless::render_headless(width, height, &out_path),
+        None => {
+            let mode = match (parse_shader_arg(&args), parse_layers_arg(&args)) {
+                (Some(path), _) => Mode::LiveShader(path),
+                (None, Some(paths)) => Mode::TextureArray(paths),
+                (None, None) => Mode::Skybox,
+            };
+            run_windowed(mode)
         },
-        pass: {
-            color: [color],
-            depth_stencil: {}
-        }
-    ).unwrap());
-
-    //GraphicsPipelineBuilder defaults to buffer-less vertex inputs as of 0.7.2
-    let pipeline = Arc::new(GraphicsPipeline::start()
-        .cull_mode_front()
-        .front_face_counter_clockwise()
-        .vertex_shader(vs.main_entry_point(), ())
-        .viewports_dynamic_scissors_irrelevant(1)
-        .fragment_shader(fs.main_entry_point(), ())
-        .render_pass(Subpass::from(render_pass.clone(), 0).unwrap())
-        .build(device.clone())
-        .unwrap());
-
-    let mut framebuffers: Option<Vec<Arc<vulkano::framebuffer::Framebuffer<_,_>>>> = None;
-    let mut recreate_swapchain = false;
-    let mut previous_frame_end = Box::new(now(device.clone())) as Box<GpuFuture>;
+    }
+}
 
-    let mut dynamic_state = DynamicState {
-        line_width: None,
-        viewports: Some(vec![Viewport {
-            origin: [0.0, 0.0],
-            dimensions: [dimensions[0] as f32, dimensions[1] as f32],
-            depth_range: 0.0 .. 1.0,
-        }]),
-        scissors: None,
+fn run_windowed(mode: Mode) {
+    let mut events_loop = EventsLoop::new();
+    let surface = SurfaceBinding::new(&events_loop);
+    let mut renderer = BufferlessRenderer::new(surface);
+    renderer = match mode {
+        Mode::Skybox => renderer,
+        Mode::LiveShader(path) => renderer.with_live_fragment_shader(path),
+        Mode::TextureArray(paths) => renderer.with_texture_array(&paths),
     };
 
     loop {
-        previous_frame_end.cleanup_finished();
-
-        if recreate_swapchain {
-            dimensions = surface
-                .capabilities(physical)
-                .expect("failed to get surface capabilities")
-                .current_extent
-                .unwrap();
-
-            let (new_swapchain, new_images) = 
-                match swapchain.recreate_with_dimension(dimensions) {
-                    Ok(r) => r,
-                    Err(SwapchainCreationError::UnsupportedDimensions) => {
-                        continue;
-                    },
-                    Err(err) => panic!("{:?}", err)
-                };
-
-            swapchain = new_swapchain;
-            images = new_images;
-
-            framebuffers = None;
-
-            dynamic_state.viewports = Some(vec![Viewport {
-                origin: [0.0, 0.0],
-                dimensions: [dimensions[0] as f32, dimensions[1] as f32],
-                depth_range: 0.0 .. 1.0,
-            }]);
-
-            recreate_swapchain = false;
-        }
-
-        if framebuffers.is_none() {
-            framebuffers = Some(images.iter().map(|image| {
-                Arc::new(Framebuffer::start(render_pass.clone())
-                    .add(image.clone())
-                    .unwrap()
-                    .build()
-                    .unwrap())
-            }).collect::<Vec<_>>());
-        }
+        renderer.draw_frame();
 
-        let (image_num, acquire_future) = 
-            match swapchain::acquire_next_image(swapchain.clone(),  None) {
-                Ok(r) => r,
-                Err(AcquireError::OutOfDate) => {
-                    recreate_swapchain = true;
-                    continue;
-                },
-                Err(err) => panic!("{:?}", err)
-            };
-
-        let command_buffer = 
-            AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue.family())
-                .unwrap()
-                .begin_render_pass(framebuffers.as_ref().unwrap()[image_num].clone(), 
-                    false, vec![[0.0, 0.0, 1.0, 1.0].into()])
-                .unwrap()
-                .draw(pipeline.clone(),
-                    &dynamic_state,
-                    BufferlessVertices{ vertices: 3, instances: 1 }, //Here's where the magic happens
-                    (), 
-                    ())
-                .unwrap()
-                .end_render_pass()
-                .unwrap()
-                .build()
-                .unwrap();
-
-        let future = previous_frame_end
-            .join(acquire_future)
-            .then_execute(queue.clone(), command_buffer)
-            .unwrap()
-            .then_swapchain_present(queue.clone(), swapchain.clone(), image_num)
-            .then_signal_fence_and_flush();
-
-        match future {
-            Ok(future) => {
-                previous_frame_end = Box::new(future) as Box<_>;
-            }
-            Err(FlushError::OutOfDate) => {
-                recreate_swapchain = true;
-                previous_frame_end = Box::new(now(device.clone())) as Box<_>;
-            }
-            Err(e) => {
-                println!("{:?}", e);
-                previous_frame_end = Box::new(now(device.clone())) as Box<_>;
-            }
-        }
-        
         let mut done = false;
         events_loop.poll_events(|ev| {
             match ev {
                 Event::WindowEvent { event: WindowEvent::CloseRequested, .. } => done = true,
+                Event::WindowEvent { event: WindowEvent::Resized(_), .. } => renderer.request_swapchain_recreation(),
+                Event::WindowEvent { event: WindowEvent::CursorMoved { position, .. }, .. } => {
+                    renderer.set_mouse_position([position.x as f32, position.y as f32]);
+                },
+                Event::WindowEvent {
+                    event: WindowEvent::KeyboardInput {
+                        input: KeyboardInput { state: ElementState::Pressed, virtual_keycode: Some(VirtualKeyCode::Space), .. },
+                        ..
+                    },
+                    ..
+                } => renderer.cycle_layer(),
                 _ => ()
             }
         });
         if done { return; }
     }
-}
\ No newline at end of file
+}
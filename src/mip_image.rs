@@ -0,0 +1,196 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use vulkano::buffer::BufferAccess;
+use vulkano::device::Device;
+use vulkano::format::FormatDesc;
+use vulkano::image::{Dimensions, ImageAccess, ImageInner, ImageLayout, ImageUsage, ImageViewAccess};
+use vulkano::image::sys::{ImageCreationError, UnsafeImage, UnsafeImageView};
+use vulkano::instance::QueueFamily;
+use vulkano::memory::DedicatedAlloc;
+use vulkano::memory::pool::{
+    AllocFromRequirementsFilter, AllocLayout, MappingRequirement, MemoryPool, MemoryPoolAlloc,
+    PotentialDedicatedAllocation, StdMemoryPool,
+};
+use vulkano::sync::{AccessError, Sharing};
+
+/// A general-purpose image that, unlike `StorageImage`, keeps the mip level
+/// count it's given instead of hardcoding one. `StorageImage`'s public
+/// constructors always pass `1` for `mipmaps` to `UnsafeImage::new`, so a
+/// texture array whose mip chain needs to be blitted in place (level 0
+/// uploaded in one command buffer submission, then each further level
+/// blitted from the one above it in a second) has nothing to blit
+/// into: an `ImmutableImage` only ever allows a single write for its whole
+/// lifetime. This mirrors `StorageImage`'s implementation (same
+/// `UnsafeImage`/`UnsafeImageView` plumbing and atomic-refcount lock) with a
+/// real mip count threaded through.
+#[derive(Debug)]
+pub(crate) struct MippedStorageImage<F> {
+    image: UnsafeImage,
+    view: UnsafeImageView,
+    memory: PotentialDedicatedAllocation<<Arc<StdMemoryPool> as MemoryPool>::Alloc>,
+    dimensions: Dimensions,
+    format: F,
+    gpu_lock: AtomicUsize,
+}
+
+impl<F> MippedStorageImage<F>
+    where F: FormatDesc
+{
+    pub(crate) fn new<'a, I>(device: Arc<Device>, dimensions: Dimensions, mip_levels: u32, format: F,
+                              usage: ImageUsage, queue_families: I)
+        -> Result<Arc<MippedStorageImage<F>>, ImageCreationError>
+        where I: IntoIterator<Item = QueueFamily<'a>>
+    {
+        let queue_families: Vec<u32> = queue_families.into_iter().map(|f| f.id()).collect();
+
+        let (image, mem_reqs) = unsafe {
+            let sharing = if queue_families.len() >= 2 {
+                Sharing::Concurrent(queue_families.iter().cloned())
+            } else {
+                Sharing::Exclusive
+            };
+
+            UnsafeImage::new(device.clone(), usage, format.format(), dimensions.to_image_dimensions(),
+                1, mip_levels, sharing, false, false)?
+        };
+
+        let mem = MemoryPool::alloc_from_requirements(&Device::standard_pool(&device), &mem_reqs,
+            AllocLayout::Optimal, MappingRequirement::DoNotMap, DedicatedAlloc::Image(&image),
+            |t| if t.is_device_local() {
+                AllocFromRequirementsFilter::Preferred
+            } else {
+                AllocFromRequirementsFilter::Allowed
+            })?;
+        unsafe {
+            image.bind_memory(mem.memory(), mem.offset())?;
+        }
+
+        let view = unsafe {
+            UnsafeImageView::raw(&image, dimensions.to_view_type(),
+                0 .. image.mipmap_levels(), 0 .. image.dimensions().array_layers())?
+        };
+
+        Ok(Arc::new(MippedStorageImage {
+            image,
+            view,
+            memory: mem,
+            dimensions,
+            format,
+            gpu_lock: AtomicUsize::new(0),
+        }))
+    }
+}
+
+unsafe impl<F> ImageAccess for MippedStorageImage<F>
+    where F: 'static + Send + Sync
+{
+    #[inline]
+    fn inner(&self) -> ImageInner {
+        ImageInner {
+            image: &self.image,
+            first_layer: 0,
+            num_layers: self.dimensions.array_layers() as usize,
+            first_mipmap_level: 0,
+            num_mipmap_levels: self.image.mipmap_levels() as usize,
+        }
+    }
+
+    #[inline]
+    fn initial_layout_requirement(&self) -> ImageLayout {
+        ImageLayout::General
+    }
+
+    #[inline]
+    fn final_layout_requirement(&self) -> ImageLayout {
+        ImageLayout::General
+    }
+
+    #[inline]
+    fn conflicts_buffer(&self, _other: &BufferAccess) -> bool {
+        false
+    }
+
+    #[inline]
+    fn conflicts_image(&self, other: &ImageAccess) -> bool {
+        self.conflict_key() == other.conflict_key()
+    }
+
+    #[inline]
+    fn conflict_key(&self) -> u64 {
+        self.image.key()
+    }
+
+    #[inline]
+    fn try_gpu_lock(&self, _exclusive_access: bool, expected_layout: ImageLayout) -> Result<(), AccessError> {
+        if expected_layout != ImageLayout::General && expected_layout != ImageLayout::Undefined {
+            return Err(AccessError::UnexpectedImageLayout {
+                requested: expected_layout,
+                allowed: ImageLayout::General,
+            });
+        }
+
+        let val = self.gpu_lock.compare_and_swap(0, 1, Ordering::SeqCst);
+        if val == 0 {
+            Ok(())
+        } else {
+            Err(AccessError::AlreadyInUse)
+        }
+    }
+
+    #[inline]
+    unsafe fn increase_gpu_lock(&self) {
+        let val = self.gpu_lock.fetch_add(1, Ordering::SeqCst);
+        debug_assert!(val >= 1);
+    }
+
+    #[inline]
+    unsafe fn unlock(&self, new_layout: Option<ImageLayout>) {
+        assert!(new_layout.is_none() || new_layout == Some(ImageLayout::General));
+        self.gpu_lock.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+unsafe impl<F> ImageViewAccess for MippedStorageImage<F>
+    where F: 'static + Send + Sync
+{
+    #[inline]
+    fn parent(&self) -> &ImageAccess {
+        self
+    }
+
+    #[inline]
+    fn dimensions(&self) -> Dimensions {
+        self.dimensions
+    }
+
+    #[inline]
+    fn inner(&self) -> &UnsafeImageView {
+        &self.view
+    }
+
+    #[inline]
+    fn descriptor_set_storage_image_layout(&self) -> ImageLayout {
+        ImageLayout::General
+    }
+
+    #[inline]
+    fn descriptor_set_combined_image_sampler_layout(&self) -> ImageLayout {
+        ImageLayout::General
+    }
+
+    #[inline]
+    fn descriptor_set_sampled_image_layout(&self) -> ImageLayout {
+        ImageLayout::General
+    }
+
+    #[inline]
+    fn descriptor_set_input_attachment_layout(&self) -> ImageLayout {
+        ImageLayout::General
+    }
+
+    #[inline]
+    fn identity_swizzle(&self) -> bool {
+        true
+    }
+}
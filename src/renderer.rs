@@ -0,0 +1,283 @@
+use std::sync::Arc;
+use std::time::Instant;
+
+use vulkano::command_buffer::{AutoCommandBufferBuilder, DynamicState};
+use vulkano::descriptor::descriptor_set::{DescriptorSet, PersistentDescriptorSet};
+use vulkano::descriptor::pipeline_layout::PipelineLayoutAbstract;
+use vulkano::pipeline::{GraphicsPipeline, GraphicsPipelineAbstract, vertex::BufferlessVertices, viewport::Viewport};
+use vulkano::swapchain as vk_swapchain;
+use vulkano::swapchain::{AcquireError, SwapchainCreationError};
+use vulkano::sync::{FlushError, GpuFuture, now};
+
+use crate::live_shader::{LiveFragmentShader, ShaderToyPushConstants};
+use crate::shaders::{array_fs, fs, vs, view_proj_push_constants};
+use crate::skybox::load_skybox;
+use crate::surface::SurfaceBinding;
+use crate::swapchain::{subpass, SwapchainBinding};
+use crate::texture_array::load_texture_array;
+
+/// Which fragment pass the renderer is currently driving. Exactly one is
+/// active at a time; switching modes rebuilds the pipeline and whatever
+/// descriptor set it needs.
+enum RenderMode {
+    Skybox { descriptor_set: Arc<dyn DescriptorSet + Send + Sync> },
+    LiveShader(LiveFragmentShader),
+    TextureArray { descriptor_set: Arc<dyn DescriptorSet + Send + Sync>, layer_count: u32, current_layer: u32 },
+}
+
+/// A ready-to-draw bufferless fullscreen pass bound to a window. Owns the
+/// surface/device/queue bindings, the swapchain and its framebuffers, the
+/// active pipeline, and whatever descriptor set the current [`RenderMode`]
+/// needs, so an embedding application only has to call `draw_frame()` once
+/// per iteration of its own event loop.
+pub struct BufferlessRenderer {
+    pub surface: SurfaceBinding,
+    pub swapchain: SwapchainBinding,
+    pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync>,
+    mode: RenderMode,
+    dynamic_state: DynamicState,
+    previous_frame_end: Box<dyn GpuFuture>,
+    recreate_swapchain: bool,
+    start_time: Instant,
+    last_mouse: [f32; 2],
+}
+
+impl BufferlessRenderer {
+    pub fn new(surface: SurfaceBinding) -> BufferlessRenderer {
+        let swapchain = SwapchainBinding::new(&surface);
+
+        let vs = vs::Shader::load(surface.device.clone()).expect("failed to create shader module");
+        let fs = fs::Shader::load(surface.device.clone()).expect("failed to create shader module");
+
+        let pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync> = Arc::new(GraphicsPipeline::start()
+            .cull_mode_front()
+            .front_face_counter_clockwise()
+            .vertex_shader(vs.main_entry_point(), ())
+            .viewports_dynamic_scissors_irrelevant(1)
+            .fragment_shader(fs.main_entry_point(), ())
+            .render_pass(subpass(&swapchain.render_pass))
+            .build(surface.device.clone())
+            .unwrap());
+
+        let (skybox_image, sampler, skybox_future) = load_skybox(surface.device.clone(), surface.graphics_queue.clone());
+
+        let layout = pipeline.descriptor_set_layout(0).unwrap();
+        let descriptor_set = Arc::new(PersistentDescriptorSet::start(layout.clone())
+            .add_sampled_image(skybox_image, sampler)
+            .unwrap()
+            .build()
+            .unwrap());
+
+        let dimensions = swapchain.dimensions;
+        let dynamic_state = dynamic_state_for(dimensions);
+
+        let previous_frame_end = Box::new(skybox_future.join(now(surface.device.clone()))) as Box<dyn GpuFuture>;
+
+        BufferlessRenderer {
+            surface,
+            swapchain,
+            pipeline,
+            mode: RenderMode::Skybox { descriptor_set },
+            dynamic_state,
+            previous_frame_end,
+            recreate_swapchain: false,
+            start_time: Instant::now(),
+            last_mouse: [0.0, 0.0],
+        }
+    }
+
+    /// Switches the renderer into Shadertoy-playground mode, building the
+    /// pipeline's fragment stage from `path` and hot-reloading it on every
+    /// subsequent `draw_frame` whenever the file's mtime changes.
+    pub fn with_live_fragment_shader(mut self, path: impl Into<std::path::PathBuf>) -> BufferlessRenderer {
+        let live_shader = LiveFragmentShader::new(path);
+        self.pipeline = live_shader.build_pipeline(self.surface.device.clone(), self.swapchain.render_pass.clone());
+        self.mode = RenderMode::LiveShader(live_shader);
+        self
+    }
+
+    /// Switches the renderer into texture-array mode, uploading `paths` as
+    /// the layers of a single `sampler2DArray` that `cycle_layer` flips
+    /// through one layer at a time.
+    pub fn with_texture_array(mut self, paths: &[String]) -> BufferlessRenderer {
+        let vs = vs::Shader::load(self.surface.device.clone()).expect("failed to create shader module");
+        let fs = array_fs::Shader::load(self.surface.device.clone()).expect("failed to create shader module");
+
+        let pipeline: Arc<dyn GraphicsPipelineAbstract + Send + Sync> = Arc::new(GraphicsPipeline::start()
+            .cull_mode_front()
+            .front_face_counter_clockwise()
+            .vertex_shader(vs.main_entry_point(), ())
+            .viewports_dynamic_scissors_irrelevant(1)
+            .fragment_shader(fs.main_entry_point(), ())
+            .render_pass(subpass(&self.swapchain.render_pass))
+            .build(self.surface.device.clone())
+            .unwrap());
+
+        let (array_image, sampler, array_future) = load_texture_array(self.surface.device.clone(), self.surface.graphics_queue.clone(), paths);
+
+        let layout = pipeline.descriptor_set_layout(0).unwrap();
+        let descriptor_set = Arc::new(PersistentDescriptorSet::start(layout.clone())
+            .add_sampled_image(array_image, sampler)
+            .unwrap()
+            .build()
+            .unwrap());
+
+        self.previous_frame_end = Box::new(self.previous_frame_end.join(array_future));
+        self.pipeline = pipeline;
+        self.mode = RenderMode::TextureArray {
+            descriptor_set,
+            layer_count: paths.len() as u32,
+            current_layer: 0,
+        };
+        self
+    }
+
+    pub fn request_swapchain_recreation(&mut self) {
+        self.recreate_swapchain = true;
+    }
+
+    pub fn set_mouse_position(&mut self, position: [f32; 2]) {
+        self.last_mouse = position;
+    }
+
+    /// Advances texture-array mode to the next layer, wrapping around. A
+    /// no-op in any other render mode.
+    pub fn cycle_layer(&mut self) {
+        if let RenderMode::TextureArray { layer_count, current_layer, .. } = &mut self.mode {
+            *current_layer = (*current_layer + 1) % *layer_count;
+        }
+    }
+
+    /// Acquires the next swapchain image, records and submits the bufferless
+    /// draw, and presents it, recreating the swapchain first if a previous
+    /// frame asked for it or the present came back out of date.
+    pub fn draw_frame(&mut self) {
+        self.previous_frame_end.cleanup_finished();
+
+        if self.recreate_swapchain {
+            let dimensions = self.surface.surface
+                .capabilities(self.surface.physical_device())
+                .expect("failed to get surface capabilities")
+                .current_extent
+                .unwrap();
+
+            match self.swapchain.recreate(dimensions) {
+                Ok(()) => {},
+                Err(SwapchainCreationError::UnsupportedDimensions) => return,
+                Err(err) => panic!("{:?}", err),
+            }
+
+            self.dynamic_state = dynamic_state_for(dimensions);
+            self.recreate_swapchain = false;
+        }
+
+        if let RenderMode::LiveShader(live_shader) = &mut self.mode {
+            if live_shader.poll_changed() {
+                self.pipeline = live_shader.build_pipeline(self.surface.device.clone(), self.swapchain.render_pass.clone());
+            }
+        }
+
+        let (image_num, acquire_future) =
+            match vk_swapchain::acquire_next_image(self.swapchain.swapchain.clone(), None) {
+                Ok(r) => r,
+                Err(AcquireError::OutOfDate) => {
+                    self.recreate_swapchain = true;
+                    return;
+                },
+                Err(err) => panic!("{:?}", err),
+            };
+
+        let dimensions = self.swapchain.dimensions;
+        let aspect = dimensions[0] as f32 / dimensions[1] as f32;
+        let elapsed = self.start_time.elapsed().as_secs_f32();
+
+        let render_pass_builder =
+            AutoCommandBufferBuilder::primary_one_time_submit(self.surface.device.clone(), self.surface.graphics_queue.family())
+                .unwrap()
+                .begin_render_pass(self.swapchain.framebuffers[image_num].clone(),
+                    false, vec![[0.0, 0.0, 1.0, 1.0].into()])
+                .unwrap();
+
+        let command_buffer = match &self.mode {
+            RenderMode::Skybox { descriptor_set } => {
+                let push_constants = view_proj_push_constants(aspect, elapsed);
+                render_pass_builder
+                    .draw(self.pipeline.clone(),
+                        &self.dynamic_state,
+                        BufferlessVertices{ vertices: 3, instances: 1 }, //Here's where the magic happens
+                        descriptor_set.clone(),
+                        push_constants)
+                    .unwrap()
+                    .end_render_pass()
+                    .unwrap()
+                    .build()
+                    .unwrap()
+            },
+            RenderMode::LiveShader(_) => {
+                let push_constants = ShaderToyPushConstants {
+                    i_resolution: [dimensions[0] as f32, dimensions[1] as f32],
+                    i_time: elapsed,
+                    _pad0: 0.0,
+                    i_mouse: self.last_mouse,
+                };
+                render_pass_builder
+                    .draw(self.pipeline.clone(),
+                        &self.dynamic_state,
+                        BufferlessVertices{ vertices: 3, instances: 1 }, //Here's where the magic happens
+                        (),
+                        push_constants)
+                    .unwrap()
+                    .end_render_pass()
+                    .unwrap()
+                    .build()
+                    .unwrap()
+            },
+            RenderMode::TextureArray { descriptor_set, current_layer, .. } => {
+                let push_constants = array_fs::ty::PushConstants { layer: *current_layer };
+                render_pass_builder
+                    .draw(self.pipeline.clone(),
+                        &self.dynamic_state,
+                        BufferlessVertices{ vertices: 3, instances: 1 }, //Here's where the magic happens
+                        descriptor_set.clone(),
+                        push_constants)
+                    .unwrap()
+                    .end_render_pass()
+                    .unwrap()
+                    .build()
+                    .unwrap()
+            },
+        };
+
+        let previous_frame_end = std::mem::replace(&mut self.previous_frame_end, Box::new(now(self.surface.device.clone())));
+        let future = previous_frame_end
+            .join(acquire_future)
+            .then_execute(self.surface.graphics_queue.clone(), command_buffer)
+            .unwrap()
+            .then_swapchain_present(self.surface.present_queue.clone(), self.swapchain.swapchain.clone(), image_num)
+            .then_signal_fence_and_flush();
+
+        match future {
+            Ok(future) => {
+                self.previous_frame_end = Box::new(future);
+            }
+            Err(FlushError::OutOfDate) => {
+                self.recreate_swapchain = true;
+            }
+            Err(e) => {
+                println!("{:?}", e);
+            }
+        }
+    }
+}
+
+fn dynamic_state_for(dimensions: [u32; 2]) -> DynamicState {
+    DynamicState {
+        line_width: None,
+        viewports: Some(vec![Viewport {
+            origin: [0.0, 0.0],
+            dimensions: [dimensions[0] as f32, dimensions[1] as f32],
+            depth_range: 0.0 .. 1.0,
+        }]),
+        scissors: None,
+    }
+}
@@ -0,0 +1,83 @@
+use cgmath::{Matrix4, Rad, SquareMatrix};
+
+//Note the vertex shader takes no inputs and instead uses gl_VertexIndex
+//to create verticies
+pub mod vs {
+    vulkano_shaders::shader!{
+        ty: "vertex",
+        src: "
+#version 450
+
+layout(location = 0) out vec2 v_clip_pos;
+
+void main() {
+    v_clip_pos = vec2((gl_VertexIndex << 1) & 2, gl_VertexIndex & 2) * 2.0f + -1.0f;
+    gl_Position = vec4(v_clip_pos, 0.0f, 1.0f);
+}
+"
+    }
+}
+
+// Reconstructs a world-space view ray from the clip-space position of the
+// bufferless fullscreen triangle and samples a cubemap along it, so the
+// whole skybox is driven by a single push-constant camera matrix.
+pub mod fs {
+    vulkano_shaders::shader!{
+        ty: "fragment",
+        src: "
+#version 450
+
+layout(location = 0) in vec2 v_clip_pos;
+layout(location = 0) out vec4 f_color;
+
+layout(push_constant) uniform PushConstants {
+    mat4 inv_view_proj;
+} push_constants;
+
+layout(set = 0, binding = 0) uniform samplerCube u_skybox;
+
+void main() {
+    vec4 world = push_constants.inv_view_proj * vec4(v_clip_pos, 1.0, 1.0);
+    vec3 dir = normalize(world.xyz / world.w);
+    f_color = texture(u_skybox, dir);
+}
+"
+    }
+}
+
+// Samples one layer of a 2D texture array, with the layer driven entirely
+// by a push constant so the host can flip between stacked fullscreen
+// textures without touching the pipeline.
+pub mod array_fs {
+    vulkano_shaders::shader!{
+        ty: "fragment",
+        src: "
+#version 450
+
+layout(location = 0) in vec2 v_clip_pos;
+layout(location = 0) out vec4 f_color;
+
+layout(push_constant) uniform PushConstants {
+    uint layer;
+} push_constants;
+
+layout(set = 0, binding = 0) uniform sampler2DArray u_layers;
+
+void main() {
+    vec2 v_screen_coords = v_clip_pos * 0.5f + 0.5f;
+    f_color = texture(u_layers, vec3(v_screen_coords, float(push_constants.layer)));
+}
+"
+    }
+}
+
+/// Builds the `fs::ty::PushConstants` for a simple orbiting camera, used by
+/// both the windowed and headless render paths.
+pub fn view_proj_push_constants(aspect: f32, elapsed_secs: f32) -> fs::ty::PushConstants {
+    let view = Matrix4::from_angle_y(Rad(elapsed_secs * 0.25));
+    let proj = cgmath::perspective(Rad(std::f32::consts::FRAC_PI_2), aspect, 0.1, 100.0);
+    let inv_view_proj = (proj * view).invert().expect("view-projection matrix not invertible");
+    fs::ty::PushConstants {
+        inv_view_proj: inv_view_proj.into(),
+    }
+}
@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use vulkano::device::{Device, Queue};
+use vulkano::format::Format;
+use vulkano::image::{Dimensions, ImmutableImage};
+use vulkano::sampler::Sampler;
+use vulkano::sync::GpuFuture;
+
+// Order matters: Dimensions::Cubemap expects the six faces concatenated as
+// +X, -X, +Y, -Y, +Z, -Z, which in left/right/top/bottom/front/back terms is
+// right, left, top, bottom, front, back. We instead load them in the order
+// the art assets are named on disk (left, right, bottom, top, back, front)
+// and rely on each file holding the face for its slot in that layout.
+const SKYBOX_FACES: [&str; 6] = [
+    "assets/skybox/left.png",
+    "assets/skybox/right.png",
+    "assets/skybox/bottom.png",
+    "assets/skybox/top.png",
+    "assets/skybox/back.png",
+    "assets/skybox/front.png",
+];
+
+fn load_skybox_bytes() -> (Vec<u8>, u32) {
+    let mut bytes = Vec::new();
+    let mut face_size = 0u32;
+
+    for path in SKYBOX_FACES.iter() {
+        let face = image::open(path)
+            .unwrap_or_else(|e| panic!("failed to load skybox face {}: {}", path, e))
+            .to_rgba();
+
+        let (width, height) = face.dimensions();
+        assert_eq!(width, height, "skybox face {} must be square", path);
+        if face_size == 0 {
+            face_size = width;
+        } else {
+            assert_eq!(face_size, width, "all skybox faces must share the same size");
+        }
+
+        bytes.extend_from_slice(&face.into_raw());
+    }
+
+    (bytes, face_size)
+}
+
+/// Uploads the six skybox faces as a cubemap and builds a sampler for it.
+/// The returned future must be joined before the cubemap is sampled from.
+pub fn load_skybox(device: Arc<Device>, queue: Arc<Queue>)
+    -> (Arc<ImmutableImage<Format>>, Arc<Sampler>, impl GpuFuture)
+{
+    let (face_bytes, face_size) = load_skybox_bytes();
+    let (skybox_image, skybox_future) = ImmutableImage::from_iter(
+        face_bytes.into_iter(),
+        Dimensions::Cubemap { size: face_size },
+        Format::R8G8B8A8Srgb,
+        queue,
+    ).expect("failed to upload skybox cubemap");
+
+    let sampler = Sampler::simple_repeat_linear(device);
+
+    (skybox_image, sampler, skybox_future)
+}
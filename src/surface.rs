@@ -0,0 +1,85 @@
+use std::sync::Arc;
+
+use vulkano::device::{Device, DeviceExtensions, Queue};
+use vulkano::instance::{Instance, PhysicalDevice};
+use vulkano::swapchain::Surface;
+
+use vulkano_win::VkSurfaceBuild;
+
+use winit::{EventsLoop, Window, WindowBuilder};
+
+/// Owns everything needed to submit work and present it to a window:
+/// the instance, the chosen physical device, the logical `Device`, and its
+/// graphics/present queues. `SwapchainBinding` is built from a reference to
+/// this and can be recreated independently of it.
+pub struct SurfaceBinding {
+    pub instance: Arc<Instance>,
+    pub surface: Arc<Surface<Window>>,
+    pub physical_device_index: usize,
+    pub device: Arc<Device>,
+    pub graphics_queue: Arc<Queue>,
+    pub present_queue: Arc<Queue>,
+}
+
+impl SurfaceBinding {
+    pub fn new(events_loop: &EventsLoop) -> SurfaceBinding {
+        let instance = {
+            let extensions = vulkano_win::required_extensions();
+            Instance::new(None, &extensions, None)
+                .expect("failed to create Vulkan instance")
+        };
+
+        let physical = PhysicalDevice::enumerate(&instance)
+            .next()
+            .expect("no device available");
+        println!("Using device: {} (type: {:?})", physical.name(), physical.ty());
+
+        let surface = WindowBuilder::new()
+            .build_vk_surface(events_loop, instance.clone())
+            .unwrap();
+
+        let graphics_family = physical
+            .queue_families()
+            .find(|&q| q.supports_graphics())
+            .expect("couldn't find a graphical queue family");
+        let present_family = physical
+            .queue_families()
+            .find(|&q| surface.is_supported(q).unwrap_or(false))
+            .expect("couldn't find a presentation queue family");
+
+        let device_ext = DeviceExtensions {
+            khr_swapchain: true,
+            .. DeviceExtensions::none()
+        };
+
+        let families = if graphics_family.id() == present_family.id() {
+            vec![(graphics_family, 0.5)]
+        } else {
+            vec![(graphics_family, 0.5), (present_family, 0.5)]
+        };
+
+        let (device, mut queues) = Device::new(physical, physical.supported_features(), &device_ext,
+                                                families.into_iter())
+            .expect("failed to create device");
+
+        let graphics_queue = queues.next().unwrap();
+        let present_queue = if graphics_family.id() == present_family.id() {
+            graphics_queue.clone()
+        } else {
+            queues.next().unwrap()
+        };
+
+        SurfaceBinding {
+            instance,
+            surface,
+            physical_device_index: physical.index(),
+            device,
+            graphics_queue,
+            present_queue,
+        }
+    }
+
+    pub fn physical_device(&self) -> PhysicalDevice {
+        PhysicalDevice::from_index(&self.instance, self.physical_device_index).unwrap()
+    }
+}
@@ -0,0 +1,96 @@
+use std::sync::Arc;
+
+use vulkano::device::Device;
+use vulkano::format::Format;
+use vulkano::framebuffer::{Framebuffer, FramebufferAbstract, RenderPassAbstract, Subpass};
+use vulkano::swapchain::{PresentMode, SurfaceTransform, Swapchain, SwapchainCreationError, SwapchainImage};
+
+use winit::Window;
+
+use crate::surface::SurfaceBinding;
+
+/// Owns the swapchain, its images, the render pass they're presented
+/// through, and the per-image framebuffers. `recreate` rebuilds the
+/// swapchain and framebuffers together so they never drift out of sync.
+pub struct SwapchainBinding {
+    pub swapchain: Arc<Swapchain<Window>>,
+    pub images: Vec<Arc<SwapchainImage<Window>>>,
+    pub render_pass: Arc<dyn RenderPassAbstract + Send + Sync>,
+    pub framebuffers: Vec<Arc<dyn FramebufferAbstract + Send + Sync>>,
+    pub dimensions: [u32; 2],
+}
+
+impl SwapchainBinding {
+    pub fn new(surface: &SurfaceBinding) -> SwapchainBinding {
+        let caps = surface.surface
+            .capabilities(surface.physical_device())
+            .expect("failed to get surface capabilities");
+
+        let dimensions = caps.current_extent.unwrap_or([1024, 768]);
+        let alpha = caps.supported_composite_alpha.iter().next().unwrap();
+        let format = caps.supported_formats[0].0;
+
+        let (swapchain, images) = Swapchain::new(surface.device.clone(), surface.surface.clone(), caps.min_image_count,
+                                                  format, dimensions, 1, caps.supported_usage_flags, &surface.graphics_queue,
+                                                  SurfaceTransform::Identity, alpha, PresentMode::Fifo, true, None)
+            .expect("failed to create swapchain");
+
+        let render_pass = build_render_pass(&surface.device, swapchain.format());
+        let framebuffers = build_framebuffers(&render_pass, &images);
+
+        SwapchainBinding {
+            swapchain,
+            images,
+            render_pass,
+            framebuffers,
+            dimensions,
+        }
+    }
+
+    /// Rebuilds the swapchain at `dimensions` and its framebuffers against
+    /// the existing render pass. Returns `Err` if `dimensions` isn't
+    /// currently supported by the surface; the caller should retry later.
+    pub fn recreate(&mut self, dimensions: [u32; 2]) -> Result<(), SwapchainCreationError> {
+        let (swapchain, images) = self.swapchain.recreate_with_dimension(dimensions)?;
+
+        self.framebuffers = build_framebuffers(&self.render_pass, &images);
+        self.swapchain = swapchain;
+        self.images = images;
+        self.dimensions = dimensions;
+
+        Ok(())
+    }
+}
+
+fn build_render_pass(device: &Arc<Device>, format: Format) -> Arc<dyn RenderPassAbstract + Send + Sync> {
+    Arc::new(single_pass_renderpass!(device.clone(),
+        attachments: {
+            color: {
+                load: Clear,
+                store: Store,
+                format: format,
+                samples: 1,
+            }
+        },
+        pass: {
+            color: [color],
+            depth_stencil: {}
+        }
+    ).unwrap())
+}
+
+fn build_framebuffers(render_pass: &Arc<dyn RenderPassAbstract + Send + Sync>, images: &[Arc<SwapchainImage<Window>>])
+    -> Vec<Arc<dyn FramebufferAbstract + Send + Sync>>
+{
+    images.iter().map(|image| {
+        Arc::new(Framebuffer::start(render_pass.clone())
+            .add(image.clone())
+            .unwrap()
+            .build()
+            .unwrap()) as Arc<dyn FramebufferAbstract + Send + Sync>
+    }).collect()
+}
+
+pub fn subpass(render_pass: &Arc<dyn RenderPassAbstract + Send + Sync>) -> Subpass<Arc<dyn RenderPassAbstract + Send + Sync>> {
+    Subpass::from(render_pass.clone(), 0).unwrap()
+}
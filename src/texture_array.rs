@@ -0,0 +1,108 @@
+use std::sync::Arc;
+
+use vulkano::buffer::{BufferUsage, CpuAccessibleBuffer};
+use vulkano::command_buffer::{AutoCommandBuffer, AutoCommandBufferBuilder};
+use vulkano::device::{Device, Queue};
+use vulkano::format::Format;
+use vulkano::image::{Dimensions, ImageUsage};
+use vulkano::sampler::{Filter, Sampler};
+use vulkano::sync::GpuFuture;
+
+use crate::mip_image::MippedStorageImage;
+
+fn load_layer_bytes(paths: &[String]) -> (Vec<u8>, u32) {
+    let mut bytes = Vec::new();
+    let mut layer_size = 0u32;
+
+    for path in paths {
+        let layer = image::open(path)
+            .unwrap_or_else(|e| panic!("failed to load texture array layer {}: {}", path, e))
+            .to_rgba();
+
+        let (width, height) = layer.dimensions();
+        assert_eq!(width, height, "texture array layer {} must be square", path);
+        if layer_size == 0 {
+            layer_size = width;
+        } else {
+            assert_eq!(layer_size, width, "all texture array layers must share the same size");
+        }
+
+        bytes.extend_from_slice(&layer.into_raw());
+    }
+
+    (bytes, layer_size)
+}
+
+/// Uploads `paths` as the layers of a single `Dim2dArray` image and generates
+/// its full mip chain, building a sampler for it. Both the upload of level 0
+/// and the per-level blits that build the rest of the chain write into the
+/// same [`MippedStorageImage`]: an `ImmutableImage` only ever allows one
+/// write for its entire lifetime, so it can't serve as the destination of
+/// both.
+pub fn load_texture_array(device: Arc<Device>, queue: Arc<Queue>, paths: &[String])
+    -> (Arc<MippedStorageImage<Format>>, Arc<Sampler>, Box<dyn GpuFuture>)
+{
+    assert!(!paths.is_empty(), "texture array needs at least one layer image");
+
+    let (bytes, layer_size) = load_layer_bytes(paths);
+    let array_layers = paths.len() as u32;
+    let mip_levels = 32 - layer_size.leading_zeros();
+    let dimensions = Dimensions::Dim2dArray { width: layer_size, height: layer_size, array_layers };
+
+    let usage = ImageUsage {
+        transfer_source: true,
+        transfer_destination: true,
+        sampled: true,
+        ..ImageUsage::none()
+    };
+
+    let image = MippedStorageImage::new(device.clone(), dimensions, mip_levels, Format::R8G8B8A8Srgb,
+        usage, device.active_queue_families())
+        .expect("failed to allocate texture array image");
+
+    let source = CpuAccessibleBuffer::from_iter(device.clone(), BufferUsage::transfer_source(), bytes.into_iter())
+        .expect("failed to stage texture array upload");
+
+    let upload_command_buffer = AutoCommandBufferBuilder::primary_one_time_submit(device.clone(), queue.family())
+        .unwrap()
+        .copy_buffer_to_image_dimensions(source, image.clone(), [0, 0, 0], dimensions.width_height_depth(), 0, dimensions.array_layers_with_cube(), 0)
+        .unwrap()
+        .build()
+        .expect("failed to build texture array upload command buffer");
+
+    let mipmap_command_buffer = build_mipmap_command_buffer(device.clone(), queue.clone(), image.clone(), layer_size, array_layers, mip_levels);
+
+    // The mip blits read back level 0 (and each level they just wrote), so
+    // they have to be sequenced after the upload completes rather than
+    // merely joined with it, or they can race the copy that initializes
+    // level 0.
+    let future = upload_command_buffer
+        .execute(queue.clone())
+        .expect("failed to submit texture array upload")
+        .then_execute(queue, mipmap_command_buffer)
+        .expect("failed to submit mipmap generation");
+
+    let sampler = Sampler::simple_repeat_linear(device);
+
+    (image, sampler, Box::new(future) as Box<dyn GpuFuture>)
+}
+
+fn build_mipmap_command_buffer(device: Arc<Device>, queue: Arc<Queue>, image: Arc<MippedStorageImage<Format>>,
+                                base_size: u32, array_layers: u32, mip_levels: u32) -> AutoCommandBuffer {
+    let mut builder = AutoCommandBufferBuilder::primary_one_time_submit(device, queue.family()).unwrap();
+
+    for level in 1 .. mip_levels {
+        let src_size = (base_size >> (level - 1)).max(1) as i32;
+        let dst_size = (base_size >> level).max(1) as i32;
+
+        builder = builder
+            .blit_image(
+                image.clone(), [0, 0, 0], [src_size, src_size, 1], 0, level - 1,
+                image.clone(), [0, 0, 0], [dst_size, dst_size, 1], 0, level,
+                array_layers, Filter::Linear,
+            )
+            .expect("failed to record mip blit");
+    }
+
+    builder.build().expect("failed to build mipmap command buffer")
+}